@@ -0,0 +1,137 @@
+//! Azure Key Vault client
+//!
+//! Once a Key Vault URL has been resolved (see [`SecretManager`](crate::SecretManager)),
+//! this module fetches the actual secret values from that vault over Azure
+//! Key Vault's REST API, authenticating as a service principal via Azure AD.
+
+use serde::Deserialize;
+use std::fmt;
+
+/// Service principal credentials used to authenticate against Azure AD.
+pub struct IdentityConfig {
+    pub tenant_id: String,
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+/// Failure modes when fetching a secret from Azure Key Vault.
+#[derive(Debug)]
+pub enum KeyVaultError {
+    /// Azure AD or the Key Vault endpoint could not be reached.
+    Unreachable(String),
+    /// The credentials were rejected, or the resulting token was rejected by Key Vault.
+    Unauthorized(String),
+    /// Any other failure, e.g. an unexpected response body.
+    Other(String),
+}
+
+impl fmt::Display for KeyVaultError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyVaultError::Unreachable(msg) => write!(f, "key vault unreachable: {msg}"),
+            KeyVaultError::Unauthorized(msg) => write!(f, "key vault authorization failed: {msg}"),
+            KeyVaultError::Other(msg) => write!(f, "key vault error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for KeyVaultError {}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct SecretResponse {
+    value: String,
+}
+
+/// Azure Key Vault is a single multi-tenant resource app; its registered
+/// audience is this fixed URI regardless of which vault is being read, not
+/// the vault's own hostname.
+const KEY_VAULT_RESOURCE_SCOPE: &str = "https://vault.azure.net/.default";
+
+/// Classifies an HTTP status code as returned by Azure AD or Key Vault.
+fn map_status_error(status: u16, message: String) -> KeyVaultError {
+    match status {
+        401 | 403 => KeyVaultError::Unauthorized(message),
+        _ => KeyVaultError::Other(message),
+    }
+}
+
+fn map_ureq_error(err: ureq::Error) -> KeyVaultError {
+    match err {
+        ureq::Error::Status(status, _) => map_status_error(status, err.to_string()),
+        ureq::Error::Transport(_) => KeyVaultError::Unreachable(err.to_string()),
+    }
+}
+
+fn fetch_access_token(identity: &IdentityConfig, scope: &str) -> Result<String, KeyVaultError> {
+    let token_url = format!(
+        "https://login.microsoftonline.com/{}/oauth2/v2.0/token",
+        identity.tenant_id
+    );
+
+    let response = ureq::post(&token_url)
+        .send_form(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", &identity.client_id),
+            ("client_secret", &identity.client_secret),
+            ("scope", scope),
+        ])
+        .map_err(map_ureq_error)?;
+
+    let token: TokenResponse = response
+        .into_json()
+        .map_err(|e| KeyVaultError::Other(e.to_string()))?;
+
+    Ok(token.access_token)
+}
+
+/// Fetches `name` from the Key Vault at `vault_url`, authenticating with `identity`.
+pub(crate) fn get_secret(
+    vault_url: &str,
+    identity: &IdentityConfig,
+    name: &str,
+) -> Result<String, KeyVaultError> {
+    let host = vault_url.trim_end_matches('/');
+    let token = fetch_access_token(identity, KEY_VAULT_RESOURCE_SCOPE)?;
+
+    let url = format!("{host}/secrets/{name}?api-version=7.4");
+    let response = ureq::get(&url)
+        .set("Authorization", &format!("Bearer {token}"))
+        .call()
+        .map_err(map_ureq_error)?;
+
+    let secret: SecretResponse = response
+        .into_json()
+        .map_err(|e| KeyVaultError::Other(e.to_string()))?;
+
+    Ok(secret.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_status_error_unauthorized_on_401_and_403() {
+        assert!(matches!(
+            map_status_error(401, "denied".to_string()),
+            KeyVaultError::Unauthorized(_)
+        ));
+        assert!(matches!(
+            map_status_error(403, "denied".to_string()),
+            KeyVaultError::Unauthorized(_)
+        ));
+    }
+
+    #[test]
+    fn test_map_status_error_other_on_unrelated_status() {
+        assert!(matches!(
+            map_status_error(500, "boom".to_string()),
+            KeyVaultError::Other(_)
+        ));
+    }
+}
@@ -0,0 +1,309 @@
+//! Secret backends
+//!
+//! A [`SecretBackend`] knows how to resolve a single secret path to its raw
+//! string value. [`SecretManager`](crate::SecretManager) holds its backend as
+//! a `Box<dyn SecretBackend>` so callers can swap 1Password for another secret
+//! store without touching the rest of the crate.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::process::Command;
+
+/// Resolves a secret at a given path to its raw value.
+pub trait SecretBackend: Send + Sync {
+    /// Reads the secret at `path` and returns its raw value.
+    fn read(&self, path: &str) -> Result<String>;
+}
+
+/// A single field of a 1Password item, as returned by `op item get --format json`.
+#[derive(Deserialize)]
+struct OpField {
+    label: Option<String>,
+    id: Option<String>,
+    value: Option<String>,
+}
+
+/// A 1Password item, as returned by `op item get --format json`.
+#[derive(Deserialize)]
+struct OpItem {
+    fields: Vec<OpField>,
+}
+
+/// Backend that shells out to the 1Password CLI (`op`).
+pub struct OnePasswordBackend;
+
+impl OnePasswordBackend {
+    /// Reads a single field from a 1Password item, identified by account,
+    /// vault and item name.
+    ///
+    /// If `op` reports that the account is not signed in, this runs
+    /// `op signin` once, exports the resulting `OP_SESSION_*` token and
+    /// retries the read.
+    pub fn read_item_field(
+        &self,
+        account: &str,
+        vault: &str,
+        item: &str,
+        field: &str,
+    ) -> Result<String> {
+        match Self::try_read_item_field(account, vault, item, field) {
+            Err(err) if Self::is_not_signed_in(&err) => {
+                Self::signin(account)?;
+                Self::try_read_item_field(account, vault, item, field)
+            }
+            result => result,
+        }
+    }
+
+    fn try_read_item_field(account: &str, vault: &str, item: &str, field: &str) -> Result<String> {
+        let output = Command::new("op")
+            .args([
+                "item",
+                "get",
+                item,
+                "--vault",
+                vault,
+                "--account",
+                account,
+                "--format",
+                "json",
+            ])
+            .output()
+            .context("Error executing command")?;
+
+        if !output.status.success() {
+            bail!(String::from_utf8_lossy(&output.stderr)
+                .trim_end()
+                .to_string());
+        }
+
+        let parsed: OpItem = serde_json::from_slice(&output.stdout)
+            .context("Failed to parse `op item get` output as JSON")?;
+
+        Self::find_field(parsed, item, field)
+    }
+
+    fn find_field(item: OpItem, item_name: &str, field: &str) -> Result<String> {
+        item.fields
+            .into_iter()
+            .find(|f| f.label.as_deref() == Some(field) || f.id.as_deref() == Some(field))
+            .and_then(|f| f.value)
+            .with_context(|| format!("field '{field}' not found on item '{item_name}'"))
+    }
+
+    fn is_not_signed_in(err: &anyhow::Error) -> bool {
+        err.to_string()
+            .to_lowercase()
+            .contains("not currently signed in")
+    }
+
+    fn signin(account: &str) -> Result<()> {
+        let output = Command::new("op")
+            .args(["signin", "--account", account, "--raw"])
+            .output()
+            .context("Error executing `op signin`")?;
+
+        let session_token = String::from_utf8(output.stdout)
+            .context("Failed to convert signin output to string")?
+            .trim_end()
+            .to_string();
+
+        env::set_var(format!("OP_SESSION_{account}"), session_token);
+        Ok(())
+    }
+}
+
+impl SecretBackend for OnePasswordBackend {
+    fn read(&self, path: &str) -> Result<String> {
+        let command = Command::new("op")
+            .arg("read")
+            .arg(path)
+            .output()
+            .context("Error executing command")?;
+
+        let value = String::from_utf8(command.stdout)
+            .context("Failed to convert command output to string")?
+            .trim_end()
+            .to_string();
+
+        Ok(value)
+    }
+}
+
+/// Backend that reads secrets from a HashiCorp Vault KV store over HTTP.
+///
+/// The server address is taken from `VAULT_ADDR`. The token is taken from
+/// `VAULT_TOKEN`, falling back to the file `~/.vault-token` written by
+/// `vault login`.
+pub struct VaultBackend {
+    addr: String,
+    token: String,
+}
+
+impl VaultBackend {
+    /// Builds a `VaultBackend` from the ambient Vault environment.
+    pub fn new() -> Result<Self> {
+        let addr = env::var("VAULT_ADDR").context("missing VAULT_ADDR environment variable")?;
+        let token = Self::resolve_token()?;
+        Ok(Self { addr, token })
+    }
+
+    fn resolve_token() -> Result<String> {
+        if let Ok(token) = env::var("VAULT_TOKEN") {
+            return Ok(token);
+        }
+
+        let home = dirs::home_dir().context("could not determine home directory")?;
+        let token_file = home.join(".vault-token");
+
+        fs::read_to_string(&token_file)
+            .with_context(|| format!("failed to read {}", token_file.display()))
+            .map(|s| s.trim_end().to_string())
+    }
+
+    /// Splits `path` into the Vault secret path and an optional `#field` suffix.
+    fn split_path(path: &str) -> (&str, Option<&str>) {
+        match path.split_once('#') {
+            Some((p, f)) => (p, Some(f)),
+            None => (path, None),
+        }
+    }
+
+    /// Picks `field` (or, if unset, the secret's sole field) out of a Vault
+    /// response's `data` object.
+    fn extract_field(
+        data: &serde_json::Value,
+        secret_path: &str,
+        field: Option<&str>,
+    ) -> Result<String> {
+        let field_name = match field {
+            Some(f) => f.to_string(),
+            None => {
+                let keys: Vec<&String> = data
+                    .as_object()
+                    .map(|m| m.keys().collect())
+                    .unwrap_or_default();
+                match keys.as_slice() {
+                    [only] => only.to_string(),
+                    _ => bail!(
+                        "path '{}' does not have exactly one field; specify one with 'path#field'",
+                        secret_path
+                    ),
+                }
+            }
+        };
+
+        data.get(&field_name)
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .with_context(|| format!("field '{}' not found in Vault secret", field_name))
+    }
+}
+
+impl SecretBackend for VaultBackend {
+    /// Reads `path` from Vault. A path may optionally end in `#field` to pick
+    /// a specific key out of the secret's `data` object; if omitted, the
+    /// `data` object must contain exactly one field.
+    fn read(&self, path: &str) -> Result<String> {
+        let (secret_path, field) = Self::split_path(path);
+
+        let url = format!("{}/v1/{}", self.addr.trim_end_matches('/'), secret_path);
+
+        let response: serde_json::Value = ureq::get(&url)
+            .set("X-Vault-Token", &self.token)
+            .call()
+            .context("failed to reach Vault server")?
+            .into_json()
+            .context("failed to parse Vault response as JSON")?;
+
+        let data = response
+            .get("data")
+            .context("Vault response missing 'data' field")?;
+
+        Self::extract_field(data, secret_path, field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_not_signed_in_matches_op_error() {
+        let err = anyhow::anyhow!(
+            "[ERROR] 2024/01/01 00:00:00 You are not currently signed in. Please run `op signin`."
+        );
+        assert!(OnePasswordBackend::is_not_signed_in(&err));
+    }
+
+    #[test]
+    fn test_is_not_signed_in_ignores_other_errors() {
+        let err = anyhow::anyhow!("[ERROR] 2024/01/01 00:00:00 item not found");
+        assert!(!OnePasswordBackend::is_not_signed_in(&err));
+    }
+
+    #[test]
+    fn test_find_field_by_label() {
+        let item: OpItem = serde_json::from_value(json!({
+            "fields": [
+                { "id": "username", "label": "username", "value": "alice" },
+                { "id": "password", "label": "password", "value": "hunter2" },
+            ]
+        }))
+        .unwrap();
+
+        let value = OnePasswordBackend::find_field(item, "demo", "password").unwrap();
+        assert_eq!(value, "hunter2");
+    }
+
+    #[test]
+    fn test_find_field_missing_is_an_error() {
+        let item: OpItem = serde_json::from_value(json!({
+            "fields": [{ "id": "username", "label": "username", "value": "alice" }]
+        }))
+        .unwrap();
+
+        let result = OnePasswordBackend::find_field(item, "demo", "password");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_split_path_with_field() {
+        assert_eq!(
+            VaultBackend::split_path("secret/data/foo#api_key"),
+            ("secret/data/foo", Some("api_key"))
+        );
+    }
+
+    #[test]
+    fn test_split_path_without_field() {
+        assert_eq!(
+            VaultBackend::split_path("secret/data/foo"),
+            ("secret/data/foo", None)
+        );
+    }
+
+    #[test]
+    fn test_extract_field_explicit() {
+        let data = json!({ "api_key": "abc123", "other": "ignored" });
+        let value = VaultBackend::extract_field(&data, "secret/data/foo", Some("api_key")).unwrap();
+        assert_eq!(value, "abc123");
+    }
+
+    #[test]
+    fn test_extract_field_infers_sole_field() {
+        let data = json!({ "value": "abc123" });
+        let value = VaultBackend::extract_field(&data, "secret/data/foo", None).unwrap();
+        assert_eq!(value, "abc123");
+    }
+
+    #[test]
+    fn test_extract_field_ambiguous_without_explicit_field_is_an_error() {
+        let data = json!({ "value": "abc123", "other": "xyz" });
+        let result = VaultBackend::extract_field(&data, "secret/data/foo", None);
+        assert!(result.is_err());
+    }
+}
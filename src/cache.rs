@@ -0,0 +1,143 @@
+//! In-memory TTL cache for secret backends
+//!
+//! Wraps a [`SecretBackend`] so repeated reads of the same path within a TTL
+//! window are served from memory instead of re-invoking `op` or hitting the
+//! network.
+
+use crate::SecretBackend;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A [`SecretBackend`] that caches reads from an inner backend for `ttl`,
+/// holding at most `max_entries` values at a time.
+pub struct CachedSecretManager {
+    backend: Box<dyn SecretBackend>,
+    ttl: Duration,
+    max_entries: usize,
+    cache: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl CachedSecretManager {
+    /// Wraps `backend` with a cache of `ttl` holding at most `max_entries` entries.
+    pub fn new(backend: Box<dyn SecretBackend>, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            backend,
+            ttl,
+            max_entries,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Removes the cached value for `path`, if any, so the next read hits the
+    /// backend again. Useful after rotating a secret.
+    pub fn invalidate(&self, path: &str) {
+        self.cache.lock().unwrap().remove(path);
+    }
+
+    /// Clears every cached value.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    fn evict_to_make_room(cache: &mut HashMap<String, (String, Instant)>, max_entries: usize) {
+        while cache.len() >= max_entries {
+            let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, (_, stored_at))| *stored_at)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            cache.remove(&oldest);
+        }
+    }
+}
+
+impl SecretBackend for CachedSecretManager {
+    fn read(&self, path: &str) -> Result<String> {
+        {
+            let cache = self.cache.lock().unwrap();
+            if let Some((value, stored_at)) = cache.get(path) {
+                if stored_at.elapsed() < self.ttl {
+                    return Ok(value.clone());
+                }
+            }
+        }
+
+        // Fetch outside the lock: `op`/network calls are the slow part this
+        // cache exists to avoid serializing across unrelated lookups.
+        let value = self.backend.read(path)?;
+
+        if self.max_entries > 0 {
+            let mut cache = self.cache.lock().unwrap();
+            if !cache.contains_key(path) {
+                Self::evict_to_make_room(&mut cache, self.max_entries);
+            }
+            cache.insert(path.to_string(), (value.clone(), Instant::now()));
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingBackend {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl SecretBackend for CountingBackend {
+        fn read(&self, path: &str) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("{path}-value"))
+        }
+    }
+
+    #[test]
+    fn test_repeated_read_within_ttl_hits_cache_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let backend = Box::new(CountingBackend {
+            calls: calls.clone(),
+        });
+        let cached = CachedSecretManager::new(backend, Duration::from_secs(60), 10);
+
+        assert_eq!(cached.read("foo").unwrap(), "foo-value");
+        assert_eq!(cached.read("foo").unwrap(), "foo-value");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_invalidate_forces_a_fresh_read() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let backend = Box::new(CountingBackend {
+            calls: calls.clone(),
+        });
+        let cached = CachedSecretManager::new(backend, Duration::from_secs(60), 10);
+
+        cached.read("foo").unwrap();
+        cached.invalidate("foo");
+        cached.read("foo").unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_zero_max_entries_disables_caching() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let backend = Box::new(CountingBackend {
+            calls: calls.clone(),
+        });
+        let cached = CachedSecretManager::new(backend, Duration::from_secs(60), 0);
+
+        cached.read("foo").unwrap();
+        cached.read("foo").unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
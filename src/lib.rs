@@ -1,15 +1,34 @@
 //! Secret Management Module
 //!
 //! This module manages secrets, retrieving Azure Key Vault URL
-//! from 1password via the command line utility `op`.
+//! from a pluggable [`SecretBackend`]. The default backend shells out to the
+//! 1Password command line utility `op`; a HashiCorp Vault backend is also
+//! available.
 //!
 
+mod azure;
+mod backend;
+mod cache;
+
+pub use azure::{IdentityConfig, KeyVaultError};
+pub use backend::{OnePasswordBackend, SecretBackend, VaultBackend};
+pub use cache::CachedSecretManager;
+
 use anyhow::{Context, Result};
-use std::process::Command;
+use serde::de::DeserializeOwned;
 
-/// URL of the Azure Key Vault.
+/// Holds the value resolved on construction (via [`SecretManager::new`],
+/// [`SecretManager::with_backend`] or [`SecretManagerBuilder`]).
+///
+/// `url` is named for the crate's original use case — reading an Azure Key
+/// Vault URL out of 1Password — but it holds whatever value the backend
+/// returned, which need not be a Key Vault URL at all. [`SecretManager::get_secret`]
+/// only works correctly when it is one; using it after `with_backend`/`builder`
+/// resolved a different kind of value fails on the Key Vault request, or sends
+/// that request to whatever host the value names.
 pub struct SecretManager {
     pub url: String,
+    backend: Box<dyn SecretBackend>,
 }
 
 impl SecretManager {
@@ -48,42 +67,188 @@ impl SecretManager {
 
         let op_path = format!("op://{}/AzureKeyVault{}/url", vault, clean_key);
 
-        let command = Command::new("op")
-            .arg("read")
-            .arg(&op_path)
-            .output()
-            .context("Error executing command")?;
+        Self::with_backend(Box::new(OnePasswordBackend), &op_path)
+    }
 
-        let url = String::from_utf8(command.stdout)
-            .context("Failed to convert command output to string")?
-            .trim_end()
-            .to_string();
+    /// Creates a new Secret Manager instance using an arbitrary [`SecretBackend`].
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - The backend to read `path` from
+    /// * `path` - The backend-specific path of the secret to retrieve
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use anyhow::Result;
+    /// use secret_manager_1password::{OnePasswordBackend, SecretManager};
+    ///
+    /// fn example() -> Result<()> {
+    ///     let secret_manager = SecretManager::with_backend(
+    ///         Box::new(OnePasswordBackend),
+    ///         "op://Test/AzureKeyVaultdemo/url",
+    ///     )?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_backend(backend: Box<dyn SecretBackend>, path: &str) -> Result<Self> {
+        let url = backend.read(path)?;
+        Ok(Self { url, backend })
+    }
 
-        Ok(Self { url })
+    /// Fetches a secret value directly from the Azure Key Vault this manager
+    /// resolved a URL for, authenticating with `identity`.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - Azure AD service principal credentials
+    /// * `name` - Name of the secret within the vault
+    pub fn get_secret(
+        &self,
+        identity: &IdentityConfig,
+        name: &str,
+    ) -> Result<String, KeyVaultError> {
+        azure::get_secret(&self.url, identity, name)
+    }
+
+    /// Starts a [`SecretManagerBuilder`] for retrieving an arbitrary field off
+    /// a 1Password item, with a configurable account, vault and item name.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use secret_manager_1password::SecretManager;
+    ///
+    /// fn example() -> Result<()> {
+    ///     let secret_manager = SecretManager::builder()
+    ///         .account("my.1password.com")
+    ///         .vault("Production")
+    ///         .item("AzureKeyVaultdemo")
+    ///         .field("url")
+    ///         .build()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn builder() -> SecretManagerBuilder {
+        SecretManagerBuilder::default()
+    }
+
+    /// Reads `name` from the backend and deserializes it as JSON into `T`,
+    /// for secrets that hold structured credentials rather than a single
+    /// value.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use anyhow::Result;
+    /// use serde::Deserialize;
+    /// use secret_manager_1password::SecretManager;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Credentials {
+    ///     api_key: String,
+    ///     endpoint: String,
+    /// }
+    ///
+    /// fn example(secret_manager: &SecretManager) -> Result<()> {
+    ///     let credentials: Credentials =
+    ///         secret_manager.get_typed("op://Production/ServiceCreds/credentials")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn get_typed<T: DeserializeOwned>(&self, name: &str) -> Result<T> {
+        let raw = self.backend.read(name)?;
+        serde_json::from_str(&raw).with_context(|| {
+            format!("failed to deserialize secret '{name}' into the requested type")
+        })
     }
 
     /// Used for testing an error is returned if the command line utility
     /// is not present.
     #[cfg(test)]
     fn wrong_command_for_test() -> Result<Self> {
-        let command = Command::new("_op_")
-            .arg("read")
-            .arg("foo")
-            .output()
-            .context("Error executing command")?;
+        struct BrokenBackend;
+
+        impl SecretBackend for BrokenBackend {
+            fn read(&self, path: &str) -> Result<String> {
+                use anyhow::Context;
+                use std::process::Command;
 
-        let url = String::from_utf8(command.stdout)
-            .context("Failed to convert command output to string")?
-            .trim_end()
-            .to_string();
+                let command = Command::new("_op_")
+                    .arg("read")
+                    .arg(path)
+                    .output()
+                    .context("Error executing command")?;
 
-        Ok(Self { url })
+                String::from_utf8(command.stdout)
+                    .context("Failed to convert command output to string")
+                    .map(|s| s.trim_end().to_string())
+            }
+        }
+
+        Self::with_backend(Box::new(BrokenBackend), "foo")
+    }
+}
+
+/// Builds a [`SecretManager`] that reads an arbitrary field off a 1Password
+/// item, rather than the fixed `AzureKeyVault{key}` / `url` convention used
+/// by [`SecretManager::new`].
+#[derive(Default)]
+pub struct SecretManagerBuilder {
+    account: Option<String>,
+    vault: Option<String>,
+    item: Option<String>,
+    field: Option<String>,
+}
+
+impl SecretManagerBuilder {
+    /// 1Password account to read the item from, e.g. `my.1password.com`.
+    pub fn account(mut self, account: impl Into<String>) -> Self {
+        self.account = Some(account.into());
+        self
+    }
+
+    /// 1Password vault the item lives in. Defaults to `Production`.
+    pub fn vault(mut self, vault: impl Into<String>) -> Self {
+        self.vault = Some(vault.into());
+        self
+    }
+
+    /// Name of the 1Password item to read.
+    pub fn item(mut self, item: impl Into<String>) -> Self {
+        self.item = Some(item.into());
+        self
+    }
+
+    /// Label (or id) of the field to read off the item. Defaults to `url`.
+    pub fn field(mut self, field: impl Into<String>) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    /// Reads the configured item field and builds the [`SecretManager`].
+    pub fn build(self) -> Result<SecretManager> {
+        let account = self.account.context("account is required")?;
+        let item = self.item.context("item is required")?;
+        let vault = self.vault.unwrap_or_else(|| "Production".to_string());
+        let field = self.field.unwrap_or_else(|| "url".to_string());
+
+        let backend = OnePasswordBackend;
+        let url = backend.read_item_field(&account, &vault, &item, &field)?;
+
+        Ok(SecretManager {
+            url,
+            backend: Box::new(backend),
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::SecretManager;
+    use crate::{SecretBackend, SecretManager};
+    use anyhow::Result;
+    use serde::Deserialize;
     use std::env;
 
     /// Tests SecretManager creation with a valid environment variable.
@@ -116,4 +281,50 @@ mod tests {
         let result = SecretManager::wrong_command_for_test();
         assert!(result.is_err());
     }
+
+    struct FixedValueBackend {
+        value: &'static str,
+    }
+
+    impl SecretBackend for FixedValueBackend {
+        fn read(&self, _path: &str) -> Result<String> {
+            Ok(self.value.to_string())
+        }
+    }
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct TestCredentials {
+        api_key: String,
+        endpoint: String,
+    }
+
+    /// Test `get_typed` deserializes well-formed JSON into the caller's type.
+    #[test]
+    fn test_get_typed_with_valid_json() {
+        let backend = FixedValueBackend {
+            value: r#"{"api_key": "abc123", "endpoint": "https://example.com"}"#,
+        };
+        let secret_manager = SecretManager::with_backend(Box::new(backend), "irrelevant").unwrap();
+
+        let credentials: TestCredentials = secret_manager.get_typed("irrelevant").unwrap();
+
+        assert_eq!(
+            credentials,
+            TestCredentials {
+                api_key: "abc123".to_string(),
+                endpoint: "https://example.com".to_string(),
+            }
+        );
+    }
+
+    /// Test `get_typed` returns an error for malformed content instead of panicking.
+    #[test]
+    fn test_get_typed_with_malformed_json() {
+        let backend = FixedValueBackend { value: "not json" };
+        let secret_manager = SecretManager::with_backend(Box::new(backend), "irrelevant").unwrap();
+
+        let result: Result<TestCredentials> = secret_manager.get_typed("irrelevant");
+
+        assert!(result.is_err());
+    }
 }